@@ -0,0 +1,36 @@
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Neg, Sub};
+
+pub mod z251;
+
+/// A finite field usable as the scalar field of a QAP/R1CS circuit.
+pub trait Field:
+    Sized
+    + Copy
+    + Clone
+    + Debug
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+
+    /// The multiplicative inverse of `self`. Undefined for `zero()`.
+    fn mul_inv(self) -> Self;
+
+    /// A generator of the field's multiplicative group, used as the coset
+    /// shift in `EvaluationDomain::coset_fft`/`coset_ifft`.
+    fn multiplicative_generator() -> Self;
+
+    /// The largest `s` such that `2^s` divides `|F*|`, i.e. the field's
+    /// two-adicity. Bounds how large an FFT domain (`m <= 2^s`)
+    /// `EvaluationDomain` can build from `root_of_unity`.
+    fn s() -> usize;
+
+    /// A primitive `2^s`-th root of unity; squaring it `s - exp` times
+    /// yields a primitive `2^exp`-th root of unity, for `exp <= s`.
+    fn root_of_unity() -> Self;
+}
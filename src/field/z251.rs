@@ -0,0 +1,90 @@
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use super::Field;
+
+/// The order of the field `Z251`.
+const MODULUS: u16 = 251;
+
+/// The finite field of integers modulo the prime 251, used throughout the
+/// crate's doctests and as the scalar field for small example circuits.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub struct Z251(u8);
+
+impl Z251 {
+    pub fn from(n: usize) -> Self {
+        Z251((n % MODULUS as usize) as u8)
+    }
+
+    pub fn inner(self) -> u8 {
+        self.0
+    }
+}
+
+impl fmt::Debug for Z251 {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.0)
+    }
+}
+
+impl Add for Z251 {
+    type Output = Z251;
+
+    fn add(self, rhs: Z251) -> Z251 {
+        Z251(((self.0 as u16 + rhs.0 as u16) % MODULUS) as u8)
+    }
+}
+
+impl Sub for Z251 {
+    type Output = Z251;
+
+    fn sub(self, rhs: Z251) -> Z251 {
+        Z251(((self.0 as u16 + MODULUS - rhs.0 as u16) % MODULUS) as u8)
+    }
+}
+
+impl Mul for Z251 {
+    type Output = Z251;
+
+    fn mul(self, rhs: Z251) -> Z251 {
+        Z251(((self.0 as u16) * (rhs.0 as u16) % MODULUS) as u8)
+    }
+}
+
+impl Neg for Z251 {
+    type Output = Z251;
+
+    fn neg(self) -> Z251 {
+        Z251(((MODULUS - self.0 as u16) % MODULUS) as u8)
+    }
+}
+
+impl Field for Z251 {
+    fn zero() -> Self {
+        Z251(0)
+    }
+
+    fn one() -> Self {
+        Z251(1)
+    }
+
+    /// Computed by Fermat's little theorem: `a^(p - 2) = a^-1 (mod p)`.
+    fn mul_inv(self) -> Self {
+        (0..MODULUS - 2).fold(Z251::one(), |acc, _| acc * self)
+    }
+
+    /// `6` generates `Z251`'s multiplicative group, which has order 250.
+    fn multiplicative_generator() -> Self {
+        Z251::from(6)
+    }
+
+    /// `250 = 2 * 5^3`, so the group's two-adicity is 1.
+    fn s() -> usize {
+        1
+    }
+
+    /// `-1`, the unique primitive 2nd root of unity.
+    fn root_of_unity() -> Self {
+        Z251::from(250)
+    }
+}
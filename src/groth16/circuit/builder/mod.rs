@@ -1,5 +1,5 @@
 use super::super::super::field::Field;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
 
 #[cfg(test)]
@@ -52,7 +52,48 @@ where
 
     wire_assignments: HashMap<WireId, Vec<ConnectionType<T>>>,
     sub_circuit_wires: HashMap<SubCircuitId, SubCircuitConnections<T>>,
-    wire_values: HashMap<WireId, Option<T>>,
+
+    /// Indexed by `WireId.0` rather than keyed in a `HashMap`, so that
+    /// `evaluate_all`'s topological pass can fill it in a single linear
+    /// scan instead of recursing through `HashMap` lookups.
+    wire_values: Vec<Option<T>>,
+    assertions: Vec<Assertion>,
+
+    /// Batched counterpart of `wire_values`: lane `i` of each `Vec` is that
+    /// wire's value for the `i`th of `batch_size` independent inputs
+    /// evaluated in lockstep. Populated by `set_word64_batch`/
+    /// `evaluate_batch`, left empty for circuits that only ever use the
+    /// scalar `evaluate`/`set_value` API.
+    wire_values_batch: HashMap<WireId, Vec<Option<T>>>,
+    batch_size: usize,
+}
+
+/// A constraint the circuit must satisfy once the witness is filled in,
+/// registered by `assert_equal`/`enforce_bit` at circuit-build time and
+/// checked by `check_witness`.
+#[derive(Clone, Copy, Debug)]
+enum Assertion {
+    /// The two wires must evaluate to the same value.
+    Equal(WireId, WireId),
+    /// `input` must be 0 or 1; `checker` is the output wire of the
+    /// `new_bit_checker` sub circuit built from it, which evaluates to
+    /// zero exactly when that holds.
+    Bit {
+        input: WireId,
+        checker: WireId,
+        sub_circuit: SubCircuitId,
+    },
+}
+
+/// Describes which registered assertion failed, so callers can report why a
+/// witness was rejected by `check_witness`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConstraintError {
+    /// The two wires did not evaluate to the same value.
+    NotEqual(WireId, WireId),
+    /// `WireId` did not evaluate to 0 or 1; `SubCircuitId` is the
+    /// bit-checker gate that caught it.
+    NotBit(WireId, SubCircuitId),
 }
 
 /// Test
@@ -61,9 +102,7 @@ where
     T: Copy + Field,
 {
     pub fn new() -> Self {
-        let mut wire_values = HashMap::new();
-        wire_values.insert(WireId(0), Some(T::zero()));
-        wire_values.insert(WireId(1), Some(T::one()));
+        let wire_values = vec![Some(T::zero()), Some(T::one())];
 
         Circuit {
             next_wire_id: WireId(2),
@@ -71,7 +110,58 @@ where
             wire_assignments: HashMap::new(),
             sub_circuit_wires: HashMap::new(),
             wire_values,
+            assertions: Vec::new(),
+            wire_values_batch: HashMap::new(),
+            batch_size: 0,
+        }
+    }
+
+    /// Registers that `a` and `b` must evaluate to the same value. Checked
+    /// by `check_witness`, not enforced while building the circuit.
+    pub fn assert_equal(&mut self, a: WireId, b: WireId) {
+        self.assertions.push(Assertion::Equal(a, b));
+    }
+
+    /// Registers that `input` must be 0 or 1, using the existing
+    /// `new_bit_checker` gate (whose output evaluates to zero iff the
+    /// input is a bit). Checked by `check_witness`.
+    pub fn enforce_bit(&mut self, input: WireId) {
+        let sub_circuit = self.next_sub_circuit_id;
+        let checker = self.new_bit_checker(input);
+        self.assertions.push(Assertion::Bit {
+            input,
+            checker,
+            sub_circuit,
+        });
+    }
+
+    /// Evaluates every registered assertion against the current wire
+    /// values, returning the first one that fails.
+    pub fn check_witness(&mut self) -> Result<(), ConstraintError>
+    where
+        T: PartialEq,
+    {
+        let assertions = self.assertions.clone();
+        for assertion in assertions {
+            match assertion {
+                Assertion::Equal(a, b) => {
+                    if self.evaluate(a) != self.evaluate(b) {
+                        return Err(ConstraintError::NotEqual(a, b));
+                    }
+                }
+                Assertion::Bit {
+                    input,
+                    checker,
+                    sub_circuit,
+                } => {
+                    if self.evaluate(checker) != T::zero() {
+                        return Err(ConstraintError::NotBit(input, sub_circuit));
+                    }
+                }
+            }
         }
+
+        Ok(())
     }
 
     /// The `Default` instances for `WireId`, `Word64`, `KeccakMatrix`,
@@ -141,6 +231,28 @@ where
         });
     }
 
+    /// Sets the values for a `Word64` across a batch of `N` independent
+    /// inputs at once, one `u64` per lane. `N` becomes this circuit's
+    /// `batch_size`, so every batched input must use the same `N`.
+    ///
+    /// See `set_word64` for the scalar equivalent.
+    pub fn set_word64_batch(&mut self, u64_wires: &Word64, inputs: &[u64]) {
+        self.batch_size = inputs.len();
+
+        let mut remaining: Vec<u64> = inputs.to_vec();
+        for &wire_id in u64_wires.iter() {
+            let values = remaining
+                .iter()
+                .map(|&n| Some(if n % 2 == 0 { T::zero() } else { T::one() }))
+                .collect();
+            self.wire_values_batch.insert(wire_id, values);
+
+            for n in remaining.iter_mut() {
+                *n >>= 1;
+            }
+        }
+    }
+
     fn set_keccakrow(&mut self, row: &KeccakRow, input: [u64; 5]) {
         row.iter()
             .zip(input.iter())
@@ -161,7 +273,7 @@ where
     pub fn new_wire(&mut self) -> WireId {
         let next_wire_id = self.next_wire_id;
         self.next_wire_id.0 += 1;
-        self.wire_values.insert(next_wire_id, None);
+        self.wire_values.push(None);
         next_wire_id
     }
 
@@ -172,12 +284,12 @@ where
     pub fn value(&self, wire: WireId) -> Option<T> {
         *self
             .wire_values
-            .get(&wire)
+            .get(wire.0)
             .expect("wire is not defined in this circuit")
     }
 
     pub fn set_value(&mut self, wire: WireId, value: T) {
-        self.wire_values.insert(wire, Some(value));
+        self.wire_values[wire.0] = Some(value);
     }
 
     pub fn wire_assignments(&self) -> &HashMap<WireId, Vec<ConnectionType<T>>> {
@@ -244,70 +356,224 @@ where
         output_wire
     }
 
-    fn evaluate_sub_circuit(&mut self, sub_circuit: SubCircuitId) -> T {
-        let SubCircuitConnections {
-            left_inputs,
-            right_inputs,
-            ..
-        } = self
+    /// Builds the dependency DAG between sub circuits (an edge `u -> v`
+    /// means `v` reads a wire that `u` produces), then returns a stable
+    /// evaluation order via Kahn's algorithm. Sub circuits whose inputs are
+    /// all already-known wires (constants or witness inputs) come first.
+    fn sub_circuit_order(&self) -> Vec<SubCircuitId> {
+        let producer: HashMap<WireId, SubCircuitId> = self
             .sub_circuit_wires
-            .get(&sub_circuit)
-            .expect("a sub circuit referenced by a wire should exist")
-            .clone();
-
-        let lhs = left_inputs
-            .into_iter()
-            .fold(T::zero(), |acc, (weight, wire)| {
-                acc + weight * self.evaluate(wire)
-            });
-        let rhs = right_inputs
-            .into_iter()
-            .fold(T::zero(), |acc, (weight, wire)| {
-                acc + weight * self.evaluate(wire)
-            });
-        lhs * rhs
+            .iter()
+            .map(|(&id, conn)| (conn.output, id))
+            .collect();
+
+        let mut in_degree: HashMap<SubCircuitId, usize> = HashMap::new();
+        let mut dependents: HashMap<SubCircuitId, Vec<SubCircuitId>> = HashMap::new();
+
+        for id in self.sub_circuits() {
+            let conn = &self.sub_circuit_wires[&id];
+            let deps: Vec<SubCircuitId> = conn
+                .left_inputs
+                .iter()
+                .chain(conn.right_inputs.iter())
+                .filter_map(|&(_, wire)| producer.get(&wire).cloned())
+                .collect();
+
+            in_degree.insert(id, deps.len());
+            for dep in deps {
+                dependents.entry(dep).or_insert_with(Vec::new).push(id);
+            }
+        }
+
+        let mut ready: VecDeque<SubCircuitId> = self
+            .sub_circuits()
+            .filter(|id| in_degree[id] == 0)
+            .collect();
+
+        let mut order = Vec::with_capacity(in_degree.len());
+        while let Some(id) = ready.pop_front() {
+            order.push(id);
+
+            if let Some(next) = dependents.get(&id) {
+                for &dependent in next {
+                    let degree = in_degree.get_mut(&dependent).unwrap();
+                    *degree -= 1;
+                    if *degree == 0 {
+                        ready.push_back(dependent);
+                    }
+                }
+            }
+        }
+
+        order
+    }
+
+    /// Fills in every wire that does not already have a value, in a single
+    /// linear pass over a topologically-ordered sub circuit DAG instead of
+    /// recursing through `evaluate`. This is what makes deep gadgets (e.g.
+    /// 24-round Keccak, chained through thousands of sub circuits) safe to
+    /// evaluate without risking a stack overflow.
+    pub fn evaluate_all(&mut self) {
+        for sub_circuit in self.sub_circuit_order() {
+            let SubCircuitConnections {
+                left_inputs,
+                right_inputs,
+                output,
+            } = self
+                .sub_circuit_wires
+                .get(&sub_circuit)
+                .expect("a sub circuit in the evaluation order should exist")
+                .clone();
+
+            if self.wire_values[output.0].is_some() {
+                continue;
+            }
+
+            let lhs = left_inputs
+                .into_iter()
+                .fold(T::zero(), |acc, (weight, wire)| {
+                    acc + weight * self.wire_values[wire.0].expect(
+                        "a sub circuit's inputs must already be evaluated by the time it is \
+                         reached in topological order",
+                    )
+                });
+            let rhs = right_inputs
+                .into_iter()
+                .fold(T::zero(), |acc, (weight, wire)| {
+                    acc + weight * self.wire_values[wire.0].expect(
+                        "a sub circuit's inputs must already be evaluated by the time it is \
+                         reached in topological order",
+                    )
+                });
+
+            self.wire_values[output.0] = Some(lhs * rhs);
+        }
     }
 
+    /// Reads `wire`'s value, running `evaluate_all` first if it has not
+    /// been computed yet.
     pub fn evaluate(&mut self, wire: WireId) -> T {
-        use self::ConnectionType::Output;
+        self.wire_values[wire.0].unwrap_or_else(|| {
+            self.evaluate_all();
+            self.wire_values[wire.0]
+                .expect("a wire with an unknown value must be the output of a sub circuit")
+        })
+    }
+
+    /// Reads `wire`'s already-computed batched value: a zero/unity
+    /// broadcast, an input set by `set_word64_batch`, or a gate output
+    /// already filled in by `evaluate_all_batch`.
+    fn wire_value_batch(&self, wire: WireId) -> Vec<T> {
+        if wire == self.zero_wire() {
+            return vec![T::zero(); self.batch_size];
+        }
+        if wire == self.unity_wire() {
+            return vec![T::one(); self.batch_size];
+        }
 
-        self.wire_values
+        self.wire_values_batch
             .get(&wire)
-            .expect("cannot evaluate unknown wire")
-            .unwrap_or_else(|| {
-                let output_sub_circuit = self
-                    .wire_assignments
-                    .get(&wire)
-                    .expect("a wire must be attached to something")
-                    .into_iter()
-                    .filter_map(|c| if let &Output(sc) = c { Some(sc) } else { None })
-                    .nth(0)
-                    .expect("a wire with an unknown value must be the output of a sub circuit");
-
-                let value = self.evaluate_sub_circuit(output_sub_circuit);
-                self.wire_values.insert(wire, Some(value));
-
-                value
-            })
+            .expect("a wire's batched value must already be computed in topological order")
+            .iter()
+            .map(|v| {
+                v.expect("a wire's batched value must already be computed in topological order")
+            }).collect()
+    }
+
+    /// Batched counterpart of `evaluate_all`: fills in every wire's batched
+    /// value in a single linear pass over the same topologically-ordered
+    /// sub circuit DAG, folding over `batch_size` lanes at each gate
+    /// instead of a single value. This keeps batched evaluation of deep
+    /// gadgets like Keccak from recursing through the stack the way the
+    /// old scalar `evaluate` used to.
+    pub fn evaluate_all_batch(&mut self) {
+        let n = self.batch_size;
+
+        for sub_circuit in self.sub_circuit_order() {
+            let SubCircuitConnections {
+                left_inputs,
+                right_inputs,
+                output,
+            } = self
+                .sub_circuit_wires
+                .get(&sub_circuit)
+                .expect("a sub circuit in the evaluation order should exist")
+                .clone();
+
+            let already_done = self
+                .wire_values_batch
+                .get(&output)
+                .map_or(false, |values| values.iter().all(Option::is_some));
+            if already_done {
+                continue;
+            }
+
+            let lhs = left_inputs
+                .into_iter()
+                .fold(vec![T::zero(); n], |acc, (weight, wire)| {
+                    let values = self.wire_value_batch(wire);
+                    acc.into_iter()
+                        .zip(values.into_iter())
+                        .map(|(a, v)| a + weight * v)
+                        .collect()
+                });
+            let rhs = right_inputs
+                .into_iter()
+                .fold(vec![T::zero(); n], |acc, (weight, wire)| {
+                    let values = self.wire_value_batch(wire);
+                    acc.into_iter()
+                        .zip(values.into_iter())
+                        .map(|(a, v)| a + weight * v)
+                        .collect()
+                });
+
+            let values: Vec<T> = lhs
+                .into_iter()
+                .zip(rhs.into_iter())
+                .map(|(l, r)| l * r)
+                .collect();
+            self.wire_values_batch
+                .insert(output, values.into_iter().map(Some).collect());
+        }
+    }
+
+    /// Reads `wire`'s batched value across the whole batch set by
+    /// `set_word64_batch`, running `evaluate_all_batch` first if it has
+    /// not been computed yet.
+    pub fn evaluate_batch(&mut self, wire: WireId) -> Vec<T> {
+        if wire == self.zero_wire() {
+            return vec![T::zero(); self.batch_size];
+        }
+        if wire == self.unity_wire() {
+            return vec![T::one(); self.batch_size];
+        }
+
+        let already_done = self
+            .wire_values_batch
+            .get(&wire)
+            .map_or(false, |values| values.iter().all(Option::is_some));
+        if !already_done {
+            self.evaluate_all_batch();
+        }
+
+        self.wire_value_batch(wire)
     }
 
-    /// Clears all of the stored circuit wire values (except for the zero and
-    /// unity wires) so that the same circuit can be reused for different
-    /// inputs.
+    /// Clears all of the stored circuit wire values, both scalar and
+    /// batched (except for the zero and unity wires), so that the same
+    /// circuit can be reused for different inputs.
     pub fn reset(&mut self) {
         let zero = self.zero_wire();
         let one = self.unity_wire();
-        let values = self.wire_values.iter_mut().filter_map(|(&k, v)| {
-            if k == zero || k == one {
-                None
-            } else {
-                Some(v)
-            }
-        });
 
-        for value in values {
-            *value = None;
+        for (index, value) in self.wire_values.iter_mut().enumerate() {
+            if index != zero.0 && index != one.0 {
+                *value = None;
+            }
         }
+
+        self.wire_values_batch.clear();
+        self.batch_size = 0;
     }
 
     pub fn new_bit_checker(&mut self, input: WireId) -> WireId {
@@ -352,6 +618,55 @@ where
         self.new_sub_circuit(lhs_inputs, rhs_inputs)
     }
 
+    /// One-bit full adder: `sum = a xor b xor carry_in`, `carry_out = (a and
+    /// b) or (carry_in and (a xor b))`. Requires that `a`, `b`, and
+    /// `carry_in` are each either 0 or 1.
+    pub fn new_full_adder(&mut self, a: WireId, b: WireId, carry_in: WireId) -> (WireId, WireId) {
+        let a_xor_b = self.new_xor(a, b);
+        let sum = self.new_xor(a_xor_b, carry_in);
+
+        let a_and_b = self.new_and(a, b);
+        let carry_and_a_xor_b = self.new_and(carry_in, a_xor_b);
+        let carry_out = self.new_or(a_and_b, carry_and_a_xor_b);
+
+        (sum, carry_out)
+    }
+
+    /// Wrapping 64-bit addition: chains 64 `new_full_adder`s from the least
+    /// to the most significant bit, discarding the final carry out.
+    pub fn word64_add(&mut self, a: &Word64, b: &Word64) -> Word64 {
+        let mut carry = self.zero_wire();
+
+        a.iter()
+            .zip(b.iter())
+            .map(|(&a_bit, &b_bit)| {
+                let (sum, carry_out) = self.new_full_adder(a_bit, b_bit, carry);
+                carry = carry_out;
+                sum
+            }).collect()
+    }
+
+    /// Addition modulo `2^width` (e.g. `width = 32` for SHA-256's lane
+    /// arithmetic): chains `width` full adders over the low bits of `a` and
+    /// `b`, discarding the carry out of the top bit. The unused high bits
+    /// of the result are wired to zero.
+    pub fn word64_add_mod(&mut self, a: &Word64, b: &Word64, width: usize) -> Word64 {
+        let mut carry = self.zero_wire();
+
+        let mut sum: Word64 = a
+            .iter()
+            .zip(b.iter())
+            .take(width)
+            .map(|(&a_bit, &b_bit)| {
+                let (bit, carry_out) = self.new_full_adder(a_bit, b_bit, carry);
+                carry = carry_out;
+                bit
+            }).collect();
+
+        sum.extend((width..64).map(|_| self.zero_wire()));
+        sum
+    }
+
     /// Requires that all inputs in array are either 0 or 1
     pub fn fan_in<F>(&mut self, inputs: &[WireId], mut gate: F) -> WireId
     where
@@ -497,7 +812,7 @@ where
     ///
     fn rotation_offsets(&mut self) -> KeccakMatrix {
         const OFFSET: [[u64; 5]; 5] = [
-            [0, 36, 3, 18, 41],
+            [0, 36, 3, 41, 18],
             [1, 44, 10, 45, 2],
             [62, 6, 43, 15, 61],
             [28, 55, 25, 21, 56],
@@ -516,20 +831,78 @@ where
 
     ///
     /// Keccak-f[b](A) {
-    ///  for i in 0…n-1
+    ///  for i in 0…23
     ///    A = Round[b](A, RC[i])
     ///  return A
     /// }
     fn keccak_f(&mut self, a: KeccakMatrix) -> KeccakMatrix {
-        (0..25).fold(a, |acc, n| self.round(acc, ROUND_CONSTANTS[n]))
+        (0..24).fold(a, |acc, n| self.round(acc, ROUND_CONSTANTS[n]))
     }
 
-    /// 1088bits end with 1...0...1 thus input is now 17 u64 which is 1024 bits
-    /// and the last u64 is 0x8000000000000001
+    /// Rate in `Word64` lanes for the Keccak-256 sponge (`r = 1088` bits,
+    /// `c = 512` bits, `r + c` = the 1600-bit state).
+    const KECCAK_RATE_WORDS: usize = 17;
+
+    /// Pads `message` with the multi-rate `pad10*1` rule so its length
+    /// becomes a multiple of `KECCAK_RATE_WORDS` `Word64`s: append the
+    /// `0x01` domain separator bit, zero-fill, then set the final bit of
+    /// the rate block. Padding is always appended: a whole block of it
+    /// when `message` is already block aligned.
+    ///
+    /// 1088bits end with 1...0...1 thus a lone padding block is 17 u64
+    /// (1088 bits): 0x0000000000000001, zeros, then 0x8000000000000000. A
+    /// single padding u64 carries both bits, 0x8000000000000001.
+    ///
+    fn keccak_pad(&mut self, message: &[Word64]) -> Vec<Word64> {
+        let remainder = message.len() % Self::KECCAK_RATE_WORDS;
+        let pad_len = Self::KECCAK_RATE_WORDS - remainder;
+
+        let mut padded: Vec<Word64> = message.to_vec();
+        for i in 0..pad_len {
+            let value = if pad_len == 1 {
+                0x8000000000000001
+            } else if i == 0 {
+                0x0000000000000001
+            } else if i == pad_len - 1 {
+                0x8000000000000000
+            } else {
+                0
+            };
+
+            let word = self.new_word64();
+            self.set_word64(&word, value);
+            padded.push(word);
+        }
+
+        padded
+    }
+
+    /// Absorbs an arbitrary-length message into a Keccak-256 sponge and
+    /// squeezes out the 256-bit digest.
+    ///
+    /// `message` is padded with `pad10*1`, split into `KECCAK_RATE_WORDS`
+    /// blocks, XORed into the first 17 lanes (row-major) of the 1600-bit
+    /// state, then permuted with `keccak_f`. The digest is read back out as
+    /// the first 256 bits (4 lanes, row-major) of the final state.
+    ///
     /// 1600 total size, 25 u64 internal 5 x 5 matrix
     ///
-    pub fn keccak(&mut self, hash: [u64; 17]) -> [u64; 0] {
-        unimplemented!();
+    pub fn keccak(&mut self, message: &[Word64]) -> [Word64; 4] {
+        let blocks = self.keccak_pad(message);
+
+        let mut state = self.new_keccakmatrix();
+        self.set_keccakmatrix(&state, [[0; 5]; 5]);
+
+        for block in blocks.chunks(Self::KECCAK_RATE_WORDS) {
+            for (i, word) in block.iter().enumerate() {
+                let (x, y) = (i % 5, i / 5);
+                state[x][y] = self.u64_bitwise_op(&state[x][y], word, Circuit::new_xor);
+            }
+
+            state = self.keccak_f(state);
+        }
+
+        [state[0][0], state[1][0], state[2][0], state[3][0]]
     }
 
     /// TODO: Use a slice instead of a Vec for the argument type.
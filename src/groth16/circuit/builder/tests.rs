@@ -0,0 +1,153 @@
+use super::super::super::super::field::z251::Z251;
+use super::*;
+
+fn word64_to_u64(circuit: &mut Circuit<Z251>, word: &Word64) -> u64 {
+    circuit.evaluate_all();
+    word.iter().enumerate().fold(0u64, |acc, (i, &wire)| {
+        if circuit.evaluate(wire) == Z251::from(0) {
+            acc
+        } else {
+            acc | (1 << i)
+        }
+    })
+}
+
+fn word64_batch_to_u64s(circuit: &mut Circuit<Z251>, word: &Word64, n: usize) -> Vec<u64> {
+    let mut result = vec![0u64; n];
+
+    for (i, &wire) in word.iter().enumerate() {
+        for (lane, value) in circuit.evaluate_batch(wire).into_iter().enumerate() {
+            if value != Z251::from(0) {
+                result[lane] |= 1 << i;
+            }
+        }
+    }
+
+    result
+}
+
+#[test]
+fn check_witness_catches_unequal_wires() {
+    let mut circuit = Circuit::<Z251>::new();
+    let a = circuit.new_wire();
+    let b = circuit.new_wire();
+    circuit.set_value(a, Z251::from(3));
+    circuit.set_value(b, Z251::from(4));
+    circuit.assert_equal(a, b);
+
+    assert_eq!(circuit.check_witness(), Err(ConstraintError::NotEqual(a, b)));
+}
+
+#[test]
+fn check_witness_catches_a_non_bit_input() {
+    let mut circuit = Circuit::<Z251>::new();
+    let input = circuit.new_wire();
+    circuit.set_value(input, Z251::from(2));
+    circuit.enforce_bit(input);
+
+    match circuit.check_witness() {
+        Err(ConstraintError::NotBit(wire, _)) => assert_eq!(wire, input),
+        other => panic!("expected Err(NotBit(..)), got {:?}", other),
+    }
+}
+
+#[test]
+fn check_witness_accepts_a_valid_witness() {
+    let mut circuit = Circuit::<Z251>::new();
+    let a = circuit.new_wire();
+    let b = circuit.new_wire();
+    circuit.set_value(a, Z251::from(1));
+    circuit.set_value(b, Z251::from(1));
+    circuit.assert_equal(a, b);
+    circuit.enforce_bit(a);
+
+    assert_eq!(circuit.check_witness(), Ok(()));
+}
+
+#[test]
+fn keccak_matches_known_test_vector_for_empty_input() {
+    let mut circuit = Circuit::<Z251>::new();
+    let digest = circuit.keccak(&[]);
+
+    // Keccak-256("") = c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470,
+    // split into 4 little-endian 64-bit lanes to match `keccak`'s squeeze order.
+    let expected: [u64; 4] = [
+        0x3c23f7860146d2c5,
+        0xc003c7dcb27d7e92,
+        0x3b2782ca53b600e5,
+        0x70a4855d04d8fa7b,
+    ];
+
+    for (lane, &expected_lane) in digest.iter().zip(expected.iter()) {
+        assert_eq!(word64_to_u64(&mut circuit, lane), expected_lane);
+    }
+}
+
+#[test]
+fn full_adder_truth_table() {
+    for &a in &[0u64, 1] {
+        for &b in &[0u64, 1] {
+            for &cin in &[0u64, 1] {
+                let mut circuit = Circuit::<Z251>::new();
+                let a_wire = circuit.new_wire();
+                let b_wire = circuit.new_wire();
+                let cin_wire = circuit.new_wire();
+                circuit.set_value(a_wire, Z251::from(a as usize));
+                circuit.set_value(b_wire, Z251::from(b as usize));
+                circuit.set_value(cin_wire, Z251::from(cin as usize));
+
+                let (sum, carry_out) = circuit.new_full_adder(a_wire, b_wire, cin_wire);
+                circuit.evaluate_all();
+
+                let total = a + b + cin;
+                assert_eq!(circuit.evaluate(sum), Z251::from((total % 2) as usize));
+                assert_eq!(circuit.evaluate(carry_out), Z251::from((total / 2) as usize));
+            }
+        }
+    }
+}
+
+#[test]
+fn word64_add_wraps_like_rust() {
+    let mut circuit = Circuit::<Z251>::new();
+    let a = circuit.new_word64();
+    let b = circuit.new_word64();
+    circuit.set_word64(&a, 0xFFFF_FFFF_FFFF_FFFF);
+    circuit.set_word64(&b, 42);
+
+    let sum = circuit.word64_add(&a, &b);
+
+    assert_eq!(
+        word64_to_u64(&mut circuit, &sum),
+        0xFFFF_FFFF_FFFF_FFFFu64.wrapping_add(42)
+    );
+}
+
+#[test]
+fn word64_add_batch_adds_each_lane_independently() {
+    let mut circuit = Circuit::<Z251>::new();
+    let a = circuit.new_word64();
+    let b = circuit.new_word64();
+    circuit.set_word64_batch(&a, &[1, 0xFFFF_FFFF_FFFF_FFFF, 42]);
+    circuit.set_word64_batch(&b, &[2, 42, 0]);
+
+    let sum = circuit.word64_add(&a, &b);
+
+    assert_eq!(
+        word64_batch_to_u64s(&mut circuit, &sum, 3),
+        vec![3, 0xFFFF_FFFF_FFFF_FFFFu64.wrapping_add(42), 42]
+    );
+}
+
+#[test]
+fn word64_add_mod_wraps_at_width() {
+    let mut circuit = Circuit::<Z251>::new();
+    let a = circuit.new_word64();
+    let b = circuit.new_word64();
+    circuit.set_word64(&a, 0xFFFF_FFFF);
+    circuit.set_word64(&b, 1);
+
+    let sum = circuit.word64_add_mod(&a, &b, 32);
+
+    assert_eq!(word64_to_u64(&mut circuit, &sum), 0);
+}
@@ -0,0 +1,180 @@
+use super::super::field::Field;
+
+#[cfg(test)]
+mod tests;
+
+/// A radix-2 evaluation domain of size `m` (a power of two) over `T`, used
+/// to move the QAP polynomials `A(x)`, `B(x)`, `C(x)` in and out of
+/// coefficient form in `O(m log m)` instead of the dense `O(m^2)`
+/// Lagrange-interpolation approach.
+///
+/// `m` is the smallest power of two at least as large as the number of
+/// constraints the domain needs to cover; `omega` is a primitive `m`-th
+/// root of unity obtained by repeatedly squaring `T::root_of_unity()`
+/// (a primitive `2^s`-th root) down to the right order.
+pub struct EvaluationDomain<T> {
+    pub m: usize,
+    pub omega: T,
+    pub omegainv: T,
+    pub geninv: T,
+    pub minv: T,
+}
+
+impl<T> EvaluationDomain<T>
+where
+    T: Copy + Field,
+{
+    /// Builds the smallest domain that can hold `needed` evaluation points.
+    ///
+    /// Panics if `needed` exceeds `2^T::s()`, the largest domain the field's
+    /// two-adicity can support.
+    pub fn new(needed: usize) -> Self {
+        let m = needed.next_power_of_two().max(1);
+        let exp = log2(m);
+
+        if exp > T::s() {
+            panic!("field does not have enough two-adicity for a domain this large");
+        }
+
+        let omega = (0..T::s() - exp).fold(T::root_of_unity(), |acc, _| acc * acc);
+        let omegainv = omega.mul_inv();
+        let geninv = T::multiplicative_generator().mul_inv();
+        let minv = from_usize::<T>(m).mul_inv();
+
+        EvaluationDomain {
+            m,
+            omega,
+            omegainv,
+            geninv,
+            minv,
+        }
+    }
+
+    /// In-place Cooley-Tukey FFT: rewrites `coeffs` (padded to length `m`)
+    /// from coefficient form into evaluations at the `m`-th roots of unity.
+    pub fn fft(&self, coeffs: &mut Vec<T>) {
+        coeffs.resize(self.m, T::zero());
+        butterfly(coeffs, self.omega);
+    }
+
+    /// In-place inverse FFT: rewrites `evals` from evaluations at the `m`-th
+    /// roots of unity back into coefficient form.
+    pub fn ifft(&self, evals: &mut Vec<T>) {
+        evals.resize(self.m, T::zero());
+        butterfly(evals, self.omegainv);
+
+        for coeff in evals.iter_mut() {
+            *coeff = *coeff * self.minv;
+        }
+    }
+
+    /// FFT on the coset `g * <omega>` instead of `<omega>` itself, which
+    /// avoids evaluating on the domain's own roots of unity (where `Z(x)`,
+    /// the vanishing polynomial, is zero).
+    pub fn coset_fft(&self, coeffs: &mut Vec<T>) {
+        distribute_powers(coeffs, T::multiplicative_generator());
+        self.fft(coeffs);
+    }
+
+    /// Inverse of `coset_fft`.
+    pub fn coset_ifft(&self, evals: &mut Vec<T>) {
+        self.ifft(evals);
+        distribute_powers(evals, self.geninv);
+    }
+
+    /// `Z(tau) = tau^m - 1`, the polynomial that vanishes on every point of
+    /// this domain.
+    pub fn z(&self, tau: T) -> T {
+        pow(tau, self.m) - T::one()
+    }
+
+    /// Divides every element of `evals` (evaluations of some polynomial on
+    /// the coset) by `Z` evaluated on that same coset, in place.
+    pub fn divide_by_z_on_coset(&self, evals: &mut Vec<T>) {
+        let z_on_coset = self.z(T::multiplicative_generator());
+        let zinv = z_on_coset.mul_inv();
+
+        for eval in evals.iter_mut() {
+            *eval = *eval * zinv;
+        }
+    }
+}
+
+/// Scales coefficient `i` of `coeffs` by `shift^i`, in place.
+fn distribute_powers<T>(coeffs: &mut Vec<T>, shift: T)
+where
+    T: Copy + Field,
+{
+    let mut power = T::one();
+    for coeff in coeffs.iter_mut() {
+        *coeff = *coeff * power;
+        power = power * shift;
+    }
+}
+
+/// In-place iterative Cooley-Tukey butterfly: bit-reverses `values`, then
+/// combines it bottom-up into the DFT with respect to `root` (an `n`-th
+/// root of unity, where `n = values.len()`).
+fn butterfly<T>(values: &mut Vec<T>, root: T)
+where
+    T: Copy + Field,
+{
+    let n = values.len();
+    bit_reverse_permute(values);
+
+    let mut len = 2;
+    while len <= n {
+        let step = pow(root, n / len);
+        let half = len / 2;
+
+        for block in values.chunks_mut(len) {
+            let mut w = T::one();
+            for i in 0..half {
+                let t = block[i + half] * w;
+                let u = block[i];
+                block[i] = u + t;
+                block[i + half] = u - t;
+                w = w * step;
+            }
+        }
+
+        len <<= 1;
+    }
+}
+
+/// Permutes `values` so that the element at index `i` moves to the index
+/// obtained by reversing the bits of `i` (within `log2(values.len())`
+/// bits), the standard precondition for an in-place iterative FFT.
+fn bit_reverse_permute<T>(values: &mut Vec<T>) {
+    let n = values.len();
+    if n <= 1 {
+        return;
+    }
+    let bits = log2(n);
+
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS as usize - bits);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+}
+
+fn pow<T>(base: T, exp: usize) -> T
+where
+    T: Copy + Field,
+{
+    (0..exp).fold(T::one(), |acc, _| acc * base)
+}
+
+fn from_usize<T>(n: usize) -> T
+where
+    T: Copy + Field,
+{
+    (0..n).fold(T::zero(), |acc, _| acc + T::one())
+}
+
+fn log2(n: usize) -> usize {
+    assert!(n.is_power_of_two(), "domain size must be a power of two");
+    n.trailing_zeros() as usize
+}
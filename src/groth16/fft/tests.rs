@@ -0,0 +1,37 @@
+use super::super::super::field::z251::Z251;
+use super::super::super::field::Field;
+use super::EvaluationDomain;
+
+#[test]
+fn fft_ifft_round_trip() {
+    let domain = EvaluationDomain::<Z251>::new(2);
+
+    let original = vec![Z251::from(3), Z251::from(5)];
+    let mut values = original.clone();
+
+    domain.fft(&mut values);
+    domain.ifft(&mut values);
+
+    assert_eq!(values, original);
+}
+
+#[test]
+fn coset_fft_coset_ifft_round_trip() {
+    let domain = EvaluationDomain::<Z251>::new(2);
+
+    let original = vec![Z251::from(7), Z251::from(11)];
+    let mut values = original.clone();
+
+    domain.coset_fft(&mut values);
+    domain.coset_ifft(&mut values);
+
+    assert_eq!(values, original);
+}
+
+#[test]
+fn z_vanishes_on_the_domain() {
+    let domain = EvaluationDomain::<Z251>::new(2);
+
+    assert_eq!(domain.z(domain.omega), Z251::zero());
+    assert_eq!(domain.z(Z251::one()), Z251::zero());
+}